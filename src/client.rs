@@ -46,7 +46,45 @@ pub struct Client {
 }
 
 /// Represent an X509 certificate.
-pub struct Certificate(native_tls::Certificate);
+///
+/// The certificate is kept around as raw DER bytes so it can be handed to
+/// whichever TLS backend (`native-tls` or `rustls`) the `Client` ends up
+/// using, converting lazily only when the connector is actually built.
+pub struct Certificate(Vec<u8>);
+
+/// Represents a private key and X509 cert as a client certificate.
+pub struct Identity(native_tls::Identity);
+
+impl Identity {
+    /// Parses a DER-formatted PKCS #12 archive, using the specified password to decrypt the key.
+    ///
+    /// The archive should contain a leaf certificate and its private key, as well any intermediate
+    /// certificates that allow clients to build a chain to a trusted root. The chain certificates
+    /// should be in order from the leaf certificate towards the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use std::io::Read;
+    /// # fn pkcs12() -> Result<(), Box<std::error::Error>> {
+    /// let mut buf = Vec::new();
+    /// File::open("my-ident.pfx")?
+    ///     .read_to_end(&mut buf)?;
+    /// let pkcs12 = reqwest::Identity::from_pkcs12_der(&buf, "my-privkey-password")?;
+    /// # drop(pkcs12);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the provided buffer is not valid DER or the password is incorrect, an error will be returned.
+    pub fn from_pkcs12_der(der: &[u8], password: &str) -> ::Result<Identity> {
+        let inner = try_!(native_tls::Identity::from_pkcs12(der, password));
+        Ok(Identity(inner))
+    }
+}
 
 impl Certificate {
     /// Create a `Certificate` from a binary DER encoded certificate
@@ -70,8 +108,64 @@ impl Certificate {
     ///
     /// If the provided buffer is not valid DER, an error will be returned.
     pub fn from_der(der: &[u8]) -> ::Result<Certificate> {
-        let inner = try_!(native_tls::Certificate::from_der(der));
-        Ok(Certificate(inner))
+        // Validate eagerly so that a bad certificate is reported at
+        // construction time rather than when the `Client` is built.
+        try_!(native_tls::Certificate::from_der(der));
+        Ok(Certificate(der.to_owned()))
+    }
+
+    /// Create a `Certificate` from a PEM encoded certificate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use std::io::Read;
+    /// # fn cert() -> Result<(), Box<std::error::Error>> {
+    /// let mut buf = Vec::new();
+    /// File::open("my_cert.pem")?
+    ///     .read_to_end(&mut buf)?;
+    /// let cert = reqwest::Certificate::from_pem(&buf)?;
+    /// # drop(cert);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Only the first PEM block in the buffer is read; any further blocks
+    /// are ignored (see `from_pem_bundle` for reading all of them). If there
+    /// is no valid PEM block, or the first one is not a valid DER encoded
+    /// certificate once decoded, an error will be returned.
+    pub fn from_pem(pem: &[u8]) -> ::Result<Certificate> {
+        let pem = try_!(::pem::parse(pem));
+        Certificate::from_der(&pem.contents)
+    }
+
+    /// Create a collection of `Certificate`s from a PEM encoded certificate
+    /// bundle.
+    ///
+    /// Files passed to this function must contain PEM encoded certificates,
+    /// concatenated together as is common with CA certificate bundles.
+    ///
+    /// # Errors
+    ///
+    /// If the provided buffer is not valid PEM, or one of the certificate
+    /// blocks is not valid DER once decoded, an error will be returned.
+    pub fn from_pem_bundle(pem_bundle: &[u8]) -> ::Result<Vec<Certificate>> {
+        let pems = try_!(::pem::parse_many(pem_bundle));
+        pems.iter()
+            .map(|pem| Certificate::from_der(&pem.contents))
+            .collect()
+    }
+
+    fn to_native_tls(&self) -> ::Result<native_tls::Certificate> {
+        Ok(try_!(native_tls::Certificate::from_der(&self.0)))
+    }
+
+    fn add_to_rustls(&self, root_store: &mut ::rustls::RootCertStore) -> ::Result<()> {
+        try_!(root_store.add(&::rustls::Certificate(self.0.clone())));
+        Ok(())
     }
 }
 
@@ -105,13 +199,115 @@ pub struct ClientBuilder {
     config: Option<Config>,
 }
 
+/// Which TLS implementation a `Client` connects through.
+///
+/// `NativeTls` is the default, and defers to the platform's TLS stack
+/// (OpenSSL, Secure Transport, SChannel, ...). `Rustls` is a pure-Rust
+/// alternative that can express things `native-tls` cannot, such as a
+/// custom server-certificate verifier.
+enum TlsBackend {
+    NativeTls,
+    Rustls,
+}
+
+impl Default for TlsBackend {
+    fn default() -> TlsBackend {
+        TlsBackend::NativeTls
+    }
+}
+
 struct Config {
     gzip: bool,
     hostname_verification: bool,
+    identity: Option<Identity>,
+    invalid_cert_hostnames: Vec<String>,
     redirect_policy: RedirectPolicy,
     referer: bool,
+    root_certs: Vec<Certificate>,
     timeout: Option<Duration>,
     tls: native_tls::TlsConnectorBuilder,
+    tls_backend: TlsBackend,
+    tls_built_in_root_certs: bool,
+    tls_webpki_roots: bool,
+}
+
+/// A `rustls::ServerCertVerifier` that accepts any certificate presented for
+/// one of an explicit list of hostnames, and otherwise falls back to the
+/// normal webpki chain and hostname validation.
+///
+/// This scopes the risk of `danger_accept_invalid_certs_for` to only the
+/// hosts the caller named, rather than disabling verification globally.
+struct AcceptHostnameCertVerifier {
+    hostnames: Vec<String>,
+}
+
+impl AcceptHostnameCertVerifier {
+    /// Whether `dns_name` is one of the explicitly allowlisted hostnames,
+    /// compared case-insensitively per RFC 6125.
+    ///
+    /// Pulled out of `verify_server_cert` so the matching logic can be unit
+    /// tested without needing a live TLS handshake.
+    fn allows(&self, dns_name: &str) -> bool {
+        self.hostnames.iter().any(|hostname| hostname.eq_ignore_ascii_case(dns_name))
+    }
+}
+
+impl ::rustls::ServerCertVerifier for AcceptHostnameCertVerifier {
+    fn verify_server_cert(
+        &self,
+        roots: &::rustls::RootCertStore,
+        presented_certs: &[::rustls::Certificate],
+        dns_name: ::webpki::DNSNameRef,
+        ocsp_response: &[u8],
+    ) -> Result<::rustls::ServerCertVerified, ::rustls::TLSError> {
+        let dns_name_str: &str = dns_name.into();
+        if self.allows(dns_name_str) {
+            return Ok(::rustls::ServerCertVerified::assertion());
+        }
+
+        ::rustls::WebPKIVerifier::new().verify_server_cert(
+            roots,
+            presented_certs,
+            dns_name,
+            ocsp_response,
+        )
+    }
+}
+
+#[cfg(test)]
+mod accept_hostname_tests {
+    use super::AcceptHostnameCertVerifier;
+
+    #[test]
+    fn allows_hostnames_on_the_list() {
+        let verifier = AcceptHostnameCertVerifier {
+            hostnames: vec!["internal.example".to_owned()],
+        };
+        assert!(verifier.allows("internal.example"));
+    }
+
+    #[test]
+    fn allows_hostnames_case_insensitively() {
+        let verifier = AcceptHostnameCertVerifier {
+            hostnames: vec!["Internal.Example".to_owned()],
+        };
+        assert!(verifier.allows("internal.example"));
+        assert!(verifier.allows("INTERNAL.EXAMPLE"));
+    }
+
+    #[test]
+    fn rejects_hostnames_not_on_the_list() {
+        let verifier = AcceptHostnameCertVerifier {
+            hostnames: vec!["internal.example".to_owned()],
+        };
+        assert!(!verifier.allows("other.example"));
+    }
+
+    #[test]
+    fn empty_allowlist_allows_nothing() {
+        let verifier = AcceptHostnameCertVerifier { hostnames: vec![] };
+        assert!(!verifier.allows("internal.example"));
+    }
 }
 
 impl ClientBuilder {
@@ -122,10 +318,16 @@ impl ClientBuilder {
             config: Some(Config {
                 gzip: true,
                 hostname_verification: true,
+                identity: None,
+                invalid_cert_hostnames: Vec::new(),
                 redirect_policy: RedirectPolicy::default(),
                 referer: true,
+                root_certs: Vec::new(),
                 timeout: None,
                 tls: tls_connector_builder,
+                tls_backend: TlsBackend::default(),
+                tls_built_in_root_certs: true,
+                tls_webpki_roots: false,
             })
         })
     }
@@ -137,20 +339,87 @@ impl ClientBuilder {
     /// This consumes the internal state of the builder. Trying to use this
     /// builder again after calling `build` will panic.
     pub fn build(&mut self) -> ::Result<Client> {
-        let config = self.take_config();
+        let mut config = self.take_config();
 
-        let tls_connector = try_!(config.tls.build());
-        let mut tls_client = NativeTlsClient::from(tls_connector);
-        if !config.hostname_verification {
-            tls_client.danger_disable_hostname_verification(true);
+        if let TlsBackend::Rustls = config.tls_backend {
+            if config.identity.is_some() {
+                return Err(tls_backend_error(
+                    "identity (client certificates) is not supported with the rustls backend",
+                ));
+            }
+            if !config.hostname_verification {
+                return Err(tls_backend_error(
+                    "danger_disable_hostname_verification is not supported with the rustls backend",
+                ));
+            }
+        } else if !config.invalid_cert_hostnames.is_empty() {
+            return Err(tls_backend_error(
+                "danger_accept_invalid_certs_for requires the rustls backend (see use_rustls_tls)",
+            ));
         }
 
-        let mut hyper_client = ::hyper::Client::with_connector(
-            ::hyper::client::Pool::with_connector(
-                Default::default(),
-                ::hyper::net::HttpsConnector::new(tls_client),
-            )
-        );
+        let mut hyper_client = match config.tls_backend {
+            TlsBackend::NativeTls => {
+                if let Some(identity) = config.identity.take() {
+                    try_!(config.tls.identity(identity.0));
+                }
+                for cert in &config.root_certs {
+                    try_!(config.tls.add_root_certificate(try_!(cert.to_native_tls())));
+                }
+
+                let tls_connector = try_!(config.tls.build());
+                let mut tls_client = NativeTlsClient::from(tls_connector);
+                if !config.hostname_verification {
+                    tls_client.danger_disable_hostname_verification(true);
+                }
+
+                ::hyper::Client::with_connector(
+                    ::hyper::client::Pool::with_connector(
+                        Default::default(),
+                        ::hyper::net::HttpsConnector::new(tls_client),
+                    )
+                )
+            }
+            TlsBackend::Rustls => {
+                let mut tls_client = ::hyper_rustls::TlsClient::new();
+                {
+                    let tls_config = Arc::get_mut(&mut tls_client.cfg)
+                        .expect("TlsClient::new() returns a unique Arc");
+
+                    if config.tls_built_in_root_certs {
+                        match ::rustls_native_certs::load_native_certs() {
+                            Ok(store) => tls_config.root_store = store,
+                            Err((Some(store), err)) => {
+                                debug!("ignoring some malformed OS root certificates: {:?}", err);
+                                tls_config.root_store = store;
+                            }
+                            Err((None, err)) => return Err(From::from(err)),
+                        }
+                    }
+                    if config.tls_webpki_roots {
+                        tls_config.root_store.add_server_trust_anchors(&::webpki_roots::TLS_SERVER_ROOTS);
+                    }
+
+                    for cert in &config.root_certs {
+                        try_!(cert.add_to_rustls(&mut tls_config.root_store));
+                    }
+                    if !config.invalid_cert_hostnames.is_empty() {
+                        tls_config.dangerous().set_certificate_verifier(Arc::new(
+                            AcceptHostnameCertVerifier {
+                                hostnames: config.invalid_cert_hostnames,
+                            },
+                        ));
+                    }
+                }
+
+                ::hyper::Client::with_connector(
+                    ::hyper::client::Pool::with_connector(
+                        Default::default(),
+                        ::hyper::net::HttpsConnector::new(tls_client),
+                    )
+                )
+            }
+        };
 
         hyper_client.set_redirect_policy(::hyper::client::RedirectPolicy::FollowNone);
         hyper_client.set_read_timeout(config.timeout);
@@ -171,10 +440,32 @@ impl ClientBuilder {
     /// This can be used to connect to a server that has a self-signed
     /// certificate for example.
     pub fn add_root_certificate(&mut self, cert: Certificate) -> ::Result<&mut ClientBuilder> {
-        try_!(self.config_mut().tls.add_root_certificate(cert.0));
+        self.config_mut().root_certs.push(cert);
         Ok(self)
     }
 
+    /// Sets the identity to be used for client certificate authentication.
+    ///
+    /// This allows connecting to servers that require mutual TLS, where the
+    /// client must present its own certificate during the handshake.
+    #[inline]
+    pub fn identity(&mut self, identity: Identity) -> &mut ClientBuilder {
+        self.config_mut().identity = Some(identity);
+        self
+    }
+
+    /// Use the `rustls` TLS backend instead of the platform's native-tls.
+    ///
+    /// `rustls` is a pure-Rust implementation, and is able to express
+    /// things native-tls cannot, such as a custom server-certificate
+    /// verifier. Like the other builder methods, this panics if called
+    /// after `build()`.
+    #[inline]
+    pub fn use_rustls_tls(&mut self) -> &mut ClientBuilder {
+        self.config_mut().tls_backend = TlsBackend::Rustls;
+        self
+    }
+
     /// Disable hostname verification.
     ///
     /// # Warning
@@ -196,6 +487,46 @@ impl ClientBuilder {
         self
     }
 
+    /// Accept invalid certificates presented for the given hostnames,
+    /// requires the `rustls` backend (see `use_rustls_tls`).
+    ///
+    /// # Warning
+    ///
+    /// Unlike `danger_disable_hostname_verification`, this only weakens
+    /// verification for the listed hosts; certificates presented for any
+    /// other host are still validated against the configured root store
+    /// as usual. Prefer this over disabling verification globally when
+    /// only a handful of dev or internal hosts need it.
+    #[inline]
+    pub fn danger_accept_invalid_certs_for(&mut self, hostnames: Vec<String>) -> &mut ClientBuilder {
+        self.config_mut().invalid_cert_hostnames = hostnames;
+        self
+    }
+
+    /// Controls the use of the platform's root certificate store.
+    ///
+    /// When using the `rustls` backend and this is enabled (the default),
+    /// the operating system's trust store is loaded into the root
+    /// certificate store before `build()` returns, the same way larger
+    /// HTTP stacks assemble their trust anchors. This can be combined with
+    /// `tls_built_in_webpki_roots` and `add_root_certificate`, both of
+    /// which are purely additive on top of whatever this loads.
+    #[inline]
+    pub fn tls_built_in_root_certs(&mut self, enabled: bool) -> &mut ClientBuilder {
+        self.config_mut().tls_built_in_root_certs = enabled;
+        self
+    }
+
+    /// Additionally load a compiled-in Mozilla ("webpki-roots") root
+    /// certificate bundle, on top of whatever `tls_built_in_root_certs`
+    /// loads. Only has an effect when using the `rustls` backend. Disabled
+    /// by default.
+    #[inline]
+    pub fn tls_built_in_webpki_roots(&mut self, enabled: bool) -> &mut ClientBuilder {
+        self.config_mut().tls_webpki_roots = enabled;
+        self
+    }
+
     /// Enable auto gzip decompression by checking the ContentEncoding response header.
     ///
     /// Default is enabled.
@@ -405,7 +736,7 @@ impl ClientRef {
                             }
                         }
                         urls.push(url);
-                        let action = check_redirect(&self.redirect_policy, &loc, &urls);
+                        let action = check_redirect(&self.redirect_policy, res.status, &loc, &urls);
 
                         match action {
                             redirect::Action::Follow => loc,
@@ -448,3 +779,9 @@ fn make_referer(next: &Url, previous: &Url) -> Option<Referer> {
     referer.set_fragment(None);
     Some(Referer(referer.into_string()))
 }
+
+/// Builds an error for a `ClientBuilder` option that isn't supported by the
+/// currently-selected TLS backend, rather than silently ignoring it.
+fn tls_backend_error(msg: &'static str) -> ::Error {
+    ::error::builder(msg)
+}
@@ -0,0 +1,141 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+use hyper::Url;
+
+/// A `Result` alias where the `Err` case is `reqwest::Error`.
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+/// The Errors that may occur when processing a `Request`.
+pub struct Error {
+    kind: Kind,
+    url: Option<Url>,
+    cause: Option<Box<StdError + Send + Sync>>,
+}
+
+#[derive(Debug)]
+enum Kind {
+    /// A lower-level error, from hyper, native-tls, rustls, I/O, etc.
+    Http,
+    /// The same URL was encountered twice while following redirects.
+    LoopDetected,
+    /// The configured maximum number of redirects was exceeded.
+    TooManyRedirects,
+    /// A `ClientBuilder` was misconfigured, e.g. a builder option that
+    /// isn't supported by the selected TLS backend.
+    Builder(String),
+}
+
+impl Error {
+    fn new(kind: Kind, cause: Option<Box<StdError + Send + Sync>>) -> Error {
+        Error {
+            kind: kind,
+            url: None,
+            cause: cause,
+        }
+    }
+
+    /// Attach the `Url` that was being requested when this error occurred.
+    pub fn with_url(mut self, url: Url) -> Error {
+        self.url = Some(url);
+        self
+    }
+
+    /// Returns the `Url` this error was associated with, if any.
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    /// Returns true if this error was caused by hitting a redirect loop.
+    pub fn is_loop_detected(&self) -> bool {
+        match self.kind {
+            Kind::LoopDetected => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if this error was caused by exceeding the configured
+    /// redirect limit.
+    pub fn is_too_many_redirects(&self) -> bool {
+        match self.kind {
+            Kind::TooManyRedirects => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if this error was caused by misconfiguring a
+    /// `ClientBuilder`.
+    pub fn is_builder(&self) -> bool {
+        match self.kind {
+            Kind::Builder(..) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut builder = f.debug_struct("reqwest::Error");
+        builder.field("kind", &self.kind);
+        if let Some(ref url) = self.url {
+            builder.field("url", url);
+        }
+        if let Some(ref cause) = self.cause {
+            builder.field("cause", cause);
+        }
+        builder.finish()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            Kind::Http => write!(f, "http error")?,
+            Kind::LoopDetected => write!(f, "infinite redirect loop detected")?,
+            Kind::TooManyRedirects => write!(f, "too many redirects")?,
+            Kind::Builder(ref msg) => write!(f, "builder error: {}", msg)?,
+        }
+        if let Some(ref url) = self.url {
+            write!(f, " for url ({})", url)?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match self.kind {
+            Kind::Http => "http error",
+            Kind::LoopDetected => "infinite redirect loop detected",
+            Kind::TooManyRedirects => "too many redirects",
+            Kind::Builder(..) => "builder error",
+        }
+    }
+
+    fn cause(&self) -> Option<&StdError> {
+        self.cause.as_ref().map(|cause| &**cause as &StdError)
+    }
+}
+
+impl<E: StdError + Send + Sync + 'static> From<E> for Error {
+    fn from(cause: E) -> Error {
+        Error::new(Kind::Http, Some(Box::new(cause)))
+    }
+}
+
+/// A redirect would repeat a `Url` already visited earlier in the chain.
+pub fn loop_detected(url: Url) -> Error {
+    Error::new(Kind::LoopDetected, None).with_url(url)
+}
+
+/// The number of redirects followed exceeded the `RedirectPolicy`'s limit.
+pub fn too_many_redirects(url: Url) -> Error {
+    Error::new(Kind::TooManyRedirects, None).with_url(url)
+}
+
+/// A `ClientBuilder` option isn't supported by the configuration it was
+/// combined with (e.g. a native-tls-only option set alongside
+/// `use_rustls_tls`).
+pub fn builder<M: Into<String>>(msg: M) -> Error {
+    Error::new(Kind::Builder(msg.into()), None)
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+
+use hyper::header::{Authorization, Cookie, Headers};
+use hyper::status::StatusCode;
+use hyper::Url;
+
+/// A type that controls the policy on how to handle the following of redirects.
+///
+/// The default value will catch redirect loops, and has a maximum of 10
+/// redirects it will follow in a chain before returning an error.
+///
+/// # Examples
+///
+/// ```
+/// # use reqwest::{Error, Client, RedirectPolicy};
+/// # fn run() -> Result<(), Error> {
+/// let client = Client::builder()?
+///     .redirect(RedirectPolicy::none())
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RedirectPolicy {
+    inner: Policy,
+}
+
+enum Policy {
+    Limit(usize),
+    None,
+    Custom(Box<Fn(RedirectAttempt) -> RedirectAction + Send + Sync>),
+}
+
+impl RedirectPolicy {
+    /// Create a `RedirectPolicy` with a maximum number of redirects.
+    ///
+    /// An `Error` will be returned if the max is reached.
+    pub fn limited(max: usize) -> RedirectPolicy {
+        RedirectPolicy { inner: Policy::Limit(max) }
+    }
+
+    /// Create a `RedirectPolicy` that does not follow any redirect.
+    pub fn none() -> RedirectPolicy {
+        RedirectPolicy { inner: Policy::None }
+    }
+
+    /// Create a custom `RedirectPolicy` using the passed function.
+    ///
+    /// The closure is called on every redirect attempt with a
+    /// `RedirectAttempt` describing the proposed next hop, and must return
+    /// a `RedirectAction` deciding what to do with it. This allows callers
+    /// to implement policies the fixed maximum cannot express, like never
+    /// downgrading from https to http, or staying on the same host.
+    ///
+    /// A redirect back to a URL already visited in the chain is reported
+    /// as a redirect loop error without calling the closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use reqwest::RedirectPolicy;
+    /// let policy = RedirectPolicy::custom(|attempt| {
+    ///     if attempt.previous().len() > 5 {
+    ///         attempt.too_many_redirects()
+    ///     } else if attempt.url().host_str() == Some("example.domain") {
+    ///         attempt.stop()
+    ///     } else {
+    ///         attempt.follow()
+    ///     }
+    /// });
+    /// ```
+    pub fn custom<T>(policy: T) -> RedirectPolicy
+        where T: Fn(RedirectAttempt) -> RedirectAction + Send + Sync + 'static
+    {
+        RedirectPolicy { inner: Policy::Custom(Box::new(policy)) }
+    }
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> RedirectPolicy {
+        RedirectPolicy::limited(10)
+    }
+}
+
+impl fmt::Debug for RedirectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            Policy::Limit(max) => write!(f, "Limit({})", max),
+            Policy::None => f.write_str("None"),
+            // The boxed closure isn't `Debug`, so just name the variant.
+            Policy::Custom(..) => f.write_str("Custom"),
+        }
+    }
+}
+
+/// A proposed redirect, passed to a custom `RedirectPolicy` for a decision.
+pub struct RedirectAttempt<'a> {
+    status: StatusCode,
+    next: &'a Url,
+    previous: &'a [Url],
+}
+
+impl<'a> RedirectAttempt<'a> {
+    /// The proposed `Url` that the next request would be sent to.
+    pub fn url(&self) -> &Url {
+        self.next
+    }
+
+    /// The status code of the response that triggered this redirect.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The URLs visited before this one, in the order they were visited.
+    pub fn previous(&self) -> &[Url] {
+        self.previous
+    }
+
+    /// Returns an action to follow the redirect.
+    pub fn follow(self) -> RedirectAction {
+        RedirectAction::Follow
+    }
+
+    /// Returns an action to stop following redirects, returning the
+    /// most recently received response as-is.
+    pub fn stop(self) -> RedirectAction {
+        RedirectAction::Stop
+    }
+
+    /// Returns an action that fails the request with a "too many redirects"
+    /// error.
+    pub fn too_many_redirects(self) -> RedirectAction {
+        RedirectAction::Error
+    }
+}
+
+/// An action taken in response to a `RedirectAttempt`.
+pub enum RedirectAction {
+    /// Follow the redirect.
+    Follow,
+    /// Don't follow the redirect, and return the received response as-is.
+    Stop,
+    /// Don't follow the redirect, and fail the request with an error.
+    Error,
+}
+
+pub enum Action {
+    Follow,
+    Stop,
+    LoopDetected,
+    TooManyRedirects,
+}
+
+pub fn check_redirect(policy: &RedirectPolicy, status: StatusCode, next: &Url, previous: &[Url]) -> Action {
+    if previous.contains(next) {
+        return Action::LoopDetected;
+    }
+
+    match policy.inner {
+        Policy::Limit(max) => {
+            if previous.len() >= max {
+                Action::TooManyRedirects
+            } else {
+                Action::Follow
+            }
+        }
+        Policy::None => Action::Stop,
+        Policy::Custom(ref custom) => {
+            let attempt = RedirectAttempt {
+                status: status,
+                next: next,
+                previous: previous,
+            };
+            match custom(attempt) {
+                RedirectAction::Follow => Action::Follow,
+                RedirectAction::Stop => Action::Stop,
+                RedirectAction::Error => Action::TooManyRedirects,
+            }
+        }
+    }
+}
+
+pub fn remove_sensitive_headers(headers: &mut Headers, next: &Url, previous: &[Url]) {
+    if let Some(previous) = previous.last() {
+        let cross_host = next.host_str() != previous.host_str() ||
+            next.port_or_known_default() != previous.port_or_known_default();
+        if cross_host {
+            headers.remove::<Authorization<String>>();
+            headers.remove::<Cookie>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn custom_policy_can_follow() {
+        let policy = RedirectPolicy::custom(|attempt| attempt.follow());
+        let next = url("http://a.example/");
+        match check_redirect(&policy, StatusCode::Found, &next, &[]) {
+            Action::Follow => {}
+            _ => panic!("expected Follow"),
+        }
+    }
+
+    #[test]
+    fn custom_policy_can_stop() {
+        let policy = RedirectPolicy::custom(|attempt| attempt.stop());
+        let next = url("http://a.example/");
+        match check_redirect(&policy, StatusCode::Found, &next, &[]) {
+            Action::Stop => {}
+            _ => panic!("expected Stop"),
+        }
+    }
+
+    #[test]
+    fn custom_policy_can_error() {
+        let policy = RedirectPolicy::custom(|attempt| attempt.too_many_redirects());
+        let next = url("http://a.example/");
+        match check_redirect(&policy, StatusCode::Found, &next, &[]) {
+            Action::TooManyRedirects => {}
+            _ => panic!("expected TooManyRedirects"),
+        }
+    }
+
+    #[test]
+    fn loop_detection_wins_over_a_custom_policy_that_always_follows() {
+        let policy = RedirectPolicy::custom(|attempt| attempt.follow());
+        let next = url("http://a.example/");
+        let previous = vec![url("http://b.example/"), next.clone()];
+        match check_redirect(&policy, StatusCode::Found, &next, &previous) {
+            Action::LoopDetected => {}
+            _ => panic!("expected LoopDetected even though the custom policy always follows"),
+        }
+    }
+
+    #[test]
+    fn limited_policy_follows_until_the_max() {
+        let policy = RedirectPolicy::limited(2);
+        let next = url("http://a.example/");
+        let previous = vec![url("http://b.example/")];
+        match check_redirect(&policy, StatusCode::Found, &next, &previous) {
+            Action::Follow => {}
+            _ => panic!("expected Follow"),
+        }
+    }
+
+    #[test]
+    fn limited_policy_stops_at_the_max() {
+        let policy = RedirectPolicy::limited(1);
+        let next = url("http://a.example/");
+        let previous = vec![url("http://b.example/")];
+        match check_redirect(&policy, StatusCode::Found, &next, &previous) {
+            Action::TooManyRedirects => {}
+            _ => panic!("expected TooManyRedirects"),
+        }
+    }
+}